@@ -0,0 +1,109 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> Pedersen<E, NUM_WINDOWS, WINDOW_SIZE> {
+    /// Returns a hiding commitment to the given input, as `Hash(input) + randomness * H`, where
+    /// `H` is an independent generator derived at `setup`. The randomness scalar multiplication
+    /// reuses the windowed fixed-base multiply introduced for `hash_uncompressed_windowed`, so its
+    /// constraint cost stays predictable regardless of the randomness bit length. The message hash
+    /// goes through `hash_uncompressed_batched` in every mode, rather than the plain per-point fold;
+    /// there is no mode-based selection between the two accumulations.
+    pub fn commit_uncompressed(&self, input: &[Boolean<E>], randomness: &[Boolean<E>]) -> Group<E> {
+        let hash = self.hash_uncompressed_batched(input);
+        let blinding = Self::mul_bits_windowed(&self.random_base, randomness);
+        hash + blinding
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_util::native_mul_bits_windowed;
+    use snarkvm_algorithms::{crh::PedersenCRH, CRH};
+    use snarkvm_circuits_environment::Circuit;
+    use snarkvm_curves::AffineCurve;
+    use snarkvm_utilities::{test_rng, UniformRand};
+
+    const ITERATIONS: usize = 10;
+    const MESSAGE: &str = "PedersenCircuit0";
+
+    type Projective = <<Circuit as Environment>::Affine as AffineCurve>::Projective;
+
+    fn check_commit_uncompressed<const NUM_WINDOWS: usize, const WINDOW_SIZE: usize>(
+        mode: Mode,
+        num_constants: usize,
+        num_public: usize,
+        num_private: usize,
+        num_constraints: usize,
+    ) {
+        // Initialize the Pedersen hash, and independently recover its randomizer generator `H`
+        // the same way `setup` derives it, so the blinding term can be checked against a native
+        // scalar multiplication rather than only against the circuit's own output.
+        let circuit = Pedersen::<Circuit, NUM_WINDOWS, WINDOW_SIZE>::setup(MESSAGE);
+        let random_base_native =
+            PedersenCRH::<Projective, 1, 1>::setup(&format!("{MESSAGE} Randomizer")).parameters()[0][0];
+        // Determine the number of inputs.
+        let num_input_bits = NUM_WINDOWS * WINDOW_SIZE;
+
+        for i in 0..ITERATIONS {
+            // Sample a random input and randomness.
+            let input = (0..num_input_bits).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            let randomness = (0..num_input_bits).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            // Prepare the circuit inputs.
+            let circuit_input: Vec<Boolean<_>> = Inject::new(mode, input);
+            let circuit_randomness: Vec<Boolean<_>> = Inject::new(mode, randomness);
+
+            // Compute the unblinded hash once, outside of the measured scope below. The flat
+            // `hash_uncompressed` and the batched accumulation `commit_uncompressed` actually uses
+            // sum the same per-window points, just in a different order, so the values agree.
+            let hash = circuit.hash_uncompressed(&circuit_input);
+
+            // Independently compute the blinding term via the native mirror of the same
+            // signed-digit windowed encoding `commit_uncompressed` uses, not a plain binary
+            // double-and-add (the two are different functions of the same bits).
+            let expected_blinding = native_mul_bits_windowed(random_base_native, &randomness).into_affine();
+
+            Circuit::scope(format!("Pedersen commit {mode} {i}"), || {
+                // Perform the commitment operation.
+                let candidate = circuit.commit_uncompressed(&circuit_input, &circuit_randomness);
+                assert_scope!(num_constants, num_public, num_private, num_constraints);
+
+                // A commitment is not expected to equal the hash without the blinding factor.
+                assert_ne!(hash.eject_value(), candidate.eject_value());
+                // The blinding term alone must match the independently-computed `randomness * H`.
+                let blinding = candidate + (-hash.clone());
+                assert_eq!(expected_blinding, blinding.eject_value());
+            });
+        }
+    }
+
+    #[test]
+    fn test_commit_uncompressed_constant() {
+        check_commit_uncompressed::<1, 8>(Mode::Constant, 64, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_commit_uncompressed_public() {
+        check_commit_uncompressed::<1, 8>(Mode::Public, 16, 0, 60, 60);
+    }
+
+    #[test]
+    fn test_commit_uncompressed_private() {
+        check_commit_uncompressed::<1, 8>(Mode::Private, 16, 0, 60, 60);
+    }
+}
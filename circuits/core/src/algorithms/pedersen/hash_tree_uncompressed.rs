@@ -0,0 +1,204 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A single domain-separation bit distinguishing leaf nodes (`false`) from parent nodes (`true`).
+const DOMAIN_FLAG_BITS: usize = 1;
+/// The number of bits used to encode each of the depth and subtree-position counters. Chosen so
+/// that a tree can grow to `2^16` leaves (and the same number of levels) without two nodes ever
+/// sharing a domain tag; `domain_separator` halts rather than silently truncating if a tree
+/// somehow grows larger than this still-generous bound.
+const DOMAIN_COUNTER_BITS: usize = 16;
+/// The total number of domain-separation bits mixed into every node of the tree.
+const DOMAIN_BITS: usize = DOMAIN_FLAG_BITS + 2 * DOMAIN_COUNTER_BITS;
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> Pedersen<E, NUM_WINDOWS, WINDOW_SIZE> {
+    /// Returns the Pedersen hash of an input of unbounded length, by splitting it into fixed-size
+    /// leaf chunks, hashing each leaf, and combining adjacent node outputs pairwise up a binary
+    /// tree (BLAKE3-style) until a single root remains.
+    ///
+    /// Every leaf and parent hash mixes in a domain-separation tag (a leaf/parent flag plus the
+    /// node's depth and subtree-position counters), so leaf and parent nodes cannot be confused and
+    /// the tree remains collision-resistant. An input that fits in a single leaf still goes through
+    /// `hash_leaf` like any other leaf (tagged with leaf index `0`), rather than through an untagged
+    /// shortcut: an untagged shortcut would let an attacker replay a legitimate two-leaf message's
+    /// already-tagged `domain_separator(true, 0, 0) ++ leaf0.to_bits_le() ++ leaf1.to_bits_le()` as
+    /// a distinct, shorter input that collides with the original root.
+    pub fn hash_tree_uncompressed(&self, input: &[Boolean<E>]) -> Group<E> {
+        let leaf_size = WINDOW_SIZE * NUM_WINDOWS;
+
+        // Split the input into fixed-size chunks and hash each into a leaf node. `slice::chunks`
+        // yields no chunks at all for an empty input, so fall back to a single empty chunk there;
+        // every input, including the empty one, is tagged and hashed identically to any other leaf
+        // instead of taking an untagged shortcut.
+        let chunks: Vec<&[Boolean<E>]> =
+            if input.is_empty() { vec![&[]] } else { input.chunks(leaf_size - DOMAIN_BITS).collect() };
+        let mut level: Vec<Group<E>> =
+            chunks.into_iter().enumerate().map(|(index, chunk)| self.hash_leaf(chunk, index)).collect();
+
+        // Combine adjacent nodes pairwise until a single root remains. An unpaired trailing node is
+        // promoted unchanged to the next level.
+        let mut depth = 0;
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .enumerate()
+                .map(|(index, pair)| match pair {
+                    [left, right] => self.hash_node(left, right, depth, index),
+                    [node] => node.clone(),
+                    _ => unreachable!("chunks(2) never yields more than two elements"),
+                })
+                .collect();
+            depth += 1;
+        }
+
+        level.remove(0)
+    }
+
+    /// Hashes a single leaf chunk, padding it to the leaf size and tagging it as a leaf node at
+    /// position `index`.
+    fn hash_leaf(&self, chunk: &[Boolean<E>], index: usize) -> Group<E> {
+        let leaf_size = WINDOW_SIZE * NUM_WINDOWS - DOMAIN_BITS;
+
+        let mut bits = chunk.to_vec();
+        bits.resize(leaf_size, Boolean::constant(false));
+
+        let mut input = Self::domain_separator(false, 0, index);
+        input.extend(bits);
+        self.hash_uncompressed_batched(&input)
+    }
+
+    /// Hashes the concatenation of two child node outputs, tagging the result as a parent node at
+    /// the given `depth` and subtree `index`.
+    fn hash_node(&self, left: &Group<E>, right: &Group<E>, depth: usize, index: usize) -> Group<E> {
+        let mut input = Self::domain_separator(true, depth, index);
+        input.extend(left.to_bits_le());
+        input.extend(right.to_bits_le());
+        if input.len() > WINDOW_SIZE * NUM_WINDOWS {
+            E::halt("pedersen window configuration is too small to hold a tree-hash parent node");
+        }
+        self.hash_uncompressed_batched(&input)
+    }
+
+    /// Returns the domain-separation bits for a node: a leaf/parent flag, followed by the node's
+    /// depth and subtree-position counters (both little-endian, `DOMAIN_COUNTER_BITS` wide).
+    ///
+    /// Halts rather than silently wrapping if `depth` or `index` do not fit in
+    /// `DOMAIN_COUNTER_BITS`, since a wraparound would give two distinct nodes the same domain tag
+    /// and defeat the purpose of the separation.
+    fn domain_separator(is_parent: bool, depth: usize, index: usize) -> Vec<Boolean<E>> {
+        if depth >= (1 << DOMAIN_COUNTER_BITS) || index >= (1 << DOMAIN_COUNTER_BITS) {
+            E::halt("pedersen tree hash exceeds the maximum supported depth or subtree position");
+        }
+
+        let mut bits = vec![Boolean::constant(is_parent)];
+        bits.extend((0..DOMAIN_COUNTER_BITS).map(|i| Boolean::constant((depth >> i) & 1 == 1)));
+        bits.extend((0..DOMAIN_COUNTER_BITS).map(|i| Boolean::constant((index >> i) & 1 == 1)));
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm_circuits_environment::Circuit;
+    use snarkvm_utilities::{test_rng, UniformRand};
+
+    const ITERATIONS: usize = 10;
+    const MESSAGE: &str = "PedersenCircuit0";
+
+    #[test]
+    fn test_hash_tree_uncompressed_tags_single_chunk_as_leaf_zero() {
+        // A single-chunk input must not take an untagged shortcut to `hash_uncompressed`: it has to
+        // go through the same `hash_leaf` tagging as every other leaf, or a replayed, already-tagged
+        // two-leaf encoding could be submitted as a colliding short message (see the no-collision
+        // test below).
+        let circuit = Pedersen::<Circuit, 8, 64>::setup(MESSAGE);
+
+        for _ in 0..ITERATIONS {
+            let input = (0..16).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            let circuit_input: Vec<Boolean<_>> = Inject::new(Mode::Private, input);
+
+            let expected = circuit.hash_leaf(&circuit_input, 0);
+            let root = circuit.hash_tree_uncompressed(&circuit_input);
+            assert_eq!(expected.eject_value(), root.eject_value());
+        }
+    }
+
+    #[test]
+    fn test_hash_tree_uncompressed_does_not_collide_with_replayed_parent_encoding() {
+        // A legitimate two-leaf message's internal parent encoding --
+        // `domain_separator(true, 0, 0) ++ leaf0.to_bits_le() ++ leaf1.to_bits_le()` -- must not be
+        // replayable as a distinct, shorter message that hashes to the same root. This is exactly
+        // the attack an untagged single-chunk shortcut would have allowed.
+        let circuit = Pedersen::<Circuit, 8, 128>::setup(MESSAGE);
+        let leaf_capacity = 8 * 128 - DOMAIN_BITS;
+
+        let message = (0..(leaf_capacity + 1)).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+        let circuit_message: Vec<Boolean<_>> = Inject::new(Mode::Private, message);
+        let root = circuit.hash_tree_uncompressed(&circuit_message);
+
+        let leaf0 = circuit.hash_leaf(&circuit_message[..leaf_capacity], 0);
+        let leaf1 = circuit.hash_leaf(&circuit_message[leaf_capacity..], 1);
+
+        let mut replayed = Pedersen::<Circuit, 8, 128>::domain_separator(true, 0, 0);
+        replayed.extend(leaf0.to_bits_le());
+        replayed.extend(leaf1.to_bits_le());
+        let replayed_root = circuit.hash_tree_uncompressed(&replayed);
+
+        assert_ne!(root.eject_value(), replayed_root.eject_value());
+    }
+
+    #[test]
+    fn test_hash_tree_uncompressed_matches_manual_combination_for_two_leaves() {
+        // A window large enough to hold the domain-separation tag plus two field elements.
+        let circuit = Pedersen::<Circuit, 8, 128>::setup(MESSAGE);
+        let leaf_capacity = 8 * 128 - DOMAIN_BITS;
+
+        for _ in 0..ITERATIONS {
+            // An input spanning exactly two leaves, the second only partially filled.
+            let input = (0..(leaf_capacity + 1)).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            let circuit_input: Vec<Boolean<_>> = Inject::new(Mode::Private, input);
+
+            // Build the expected root by hand, using the same leaf/node recipe documented above.
+            let leaf0 = circuit.hash_leaf(&circuit_input[..leaf_capacity], 0);
+            let leaf1 = circuit.hash_leaf(&circuit_input[leaf_capacity..], 1);
+            let expected_root = circuit.hash_node(&leaf0, &leaf1, 0, 0);
+
+            let root = circuit.hash_tree_uncompressed(&circuit_input);
+            assert_eq!(expected_root.eject_value(), root.eject_value());
+        }
+    }
+
+    #[test]
+    fn test_hash_leaf_and_hash_node_domain_separate_by_position() {
+        let circuit = Pedersen::<Circuit, 8, 128>::setup(MESSAGE);
+        let leaf_capacity = 8 * 128 - DOMAIN_BITS;
+
+        let chunk = (0..leaf_capacity).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+        let circuit_chunk: Vec<Boolean<_>> = Inject::new(Mode::Private, chunk);
+
+        // Hashing the same bits as two different leaf positions must not collide.
+        let leaf_at_0 = circuit.hash_leaf(&circuit_chunk, 0);
+        let leaf_at_1 = circuit.hash_leaf(&circuit_chunk, 1);
+        assert_ne!(leaf_at_0.eject_value(), leaf_at_1.eject_value());
+
+        // Nor must a leaf collide with a parent node built from the same child bits.
+        let parent = circuit.hash_node(&leaf_at_0, &leaf_at_0, 0, 0);
+        assert_ne!(leaf_at_0.eject_value(), parent.eject_value());
+    }
+}
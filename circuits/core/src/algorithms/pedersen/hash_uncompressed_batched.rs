@@ -0,0 +1,255 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use std::borrow::Cow;
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> Pedersen<E, NUM_WINDOWS, WINDOW_SIZE> {
+    /// Returns the Pedersen hash of the given input, identically to `hash_uncompressed`, but sums
+    /// the per-window selected points with a pairwise tree of additions instead of a single fold,
+    /// batch-normalizing every level's chord slopes with one inversion (Montgomery's trick) instead
+    /// of paying one inversion per addition. This is the recommended entry point for
+    /// `Mode::Private`/`Mode::Public` evaluation, where many windows are present; `hash_uncompressed`
+    /// remains available for callers that want the per-point behavior.
+    pub fn hash_uncompressed_batched(&self, input: &[Boolean<E>]) -> Group<E> {
+        let constant_false = Boolean::<E>::constant(false);
+
+        let mut input = Cow::Borrowed(input);
+        match input.len() <= WINDOW_SIZE * NUM_WINDOWS {
+            // Pad the input if it is under the required parameter size.
+            true => input.to_mut().resize(WINDOW_SIZE * NUM_WINDOWS, constant_false),
+            // Ensure the input size is within the parameter size,
+            false => E::halt("incorrect input length for pedersen hash"),
+        }
+
+        // Select the per-window points, exactly as `hash_uncompressed` does. Roughly half of these
+        // are the identity (wherever the corresponding bit is `false`), so the summation below must
+        // not assume every pair it adds is in general position.
+        let selected_points = input
+            .chunks(WINDOW_SIZE)
+            .zip_eq(&self.bases)
+            .flat_map(|(bits, powers)| {
+                bits.iter()
+                    .zip_eq(powers)
+                    .map(|(bit, base)| Group::<E>::ternary(bit, base, &Group::<E>::zero()))
+                    .collect::<Vec<Group<E>>>()
+            })
+            .collect::<Vec<Group<E>>>();
+
+        Self::sum_batched(&selected_points)
+    }
+
+    /// Sums `points` using a pairwise tree of additions: each level pairs up adjacent points (an
+    /// unpaired trailing point is promoted unchanged), and every pair in that level is normalized
+    /// with a single batched inversion instead of one inversion per pair.
+    ///
+    /// Unlike a sequential fold, the pairs within one level are mutually independent, so their
+    /// chord-slope denominators can be computed up front and inverted together; a sequential running
+    /// sum cannot be batched this way, since each step's denominator depends on the *previous*
+    /// step's (uninverted) result.
+    fn sum_batched(points: &[Group<E>]) -> Group<E> {
+        if points.is_empty() {
+            return Group::zero();
+        }
+
+        let mut level = points.to_vec();
+        while level.len() > 1 {
+            level = Self::sum_pairs_batched(&level);
+        }
+        level.remove(0)
+    }
+
+    /// Pairwise-sums adjacent points in `level` with one batched inversion for the whole level.
+    ///
+    /// A pair where either point is the identity (as happens whenever a Pedersen window bit was
+    /// `false`) is resolved directly to the other operand via `ternary`, rather than through the
+    /// chord formula, which only holds for two points in general position. The chord formula's
+    /// denominator, `x1 - x2`, is also zero in two other cases: `a == b` (the pair addition is
+    /// really a doubling) and `a == -b` (the pair are additive inverses, so they also share an
+    /// x-coordinate and sum to the identity). This assumes neither case arises among the
+    /// non-identity points in any given pair, which holds with overwhelming probability since the
+    /// Pedersen window bases (and their signed-digit multiples) are independent.
+    fn sum_pairs_batched(level: &[Group<E>]) -> Vec<Group<E>> {
+        let zero = Group::<E>::zero();
+
+        let pairs: Vec<(Group<E>, Group<E>)> = level
+            .chunks(2)
+            .filter_map(|chunk| match chunk {
+                [a, b] => Some((a.clone(), b.clone())),
+                _ => None,
+            })
+            .collect();
+
+        let a_is_zero: Vec<Boolean<E>> = pairs.iter().map(|(a, _)| a.is_equal(&zero)).collect();
+        let b_is_zero: Vec<Boolean<E>> = pairs.iter().map(|(_, b)| b.is_equal(&zero)).collect();
+
+        // Every pair's chord-slope denominator, batch-inverted in one pass. A pair touching the
+        // identity gets a dummy nonzero denominator; its result is resolved via `ternary` below and
+        // never uses this inverse, so the dummy value never surfaces.
+        let denominators: Vec<Field<E>> = pairs
+            .iter()
+            .zip_eq(&a_is_zero)
+            .zip_eq(&b_is_zero)
+            .map(|((pair, a_zero), b_zero)| {
+                let (a, b) = pair;
+                let real_denominator = a.to_x_coordinate() - b.to_x_coordinate();
+                Field::ternary(&(a_zero.clone() | b_zero.clone()), &Field::one(), &real_denominator)
+            })
+            .collect();
+        let inverses = Self::batch_invert(&denominators);
+
+        let mut sums: Vec<Group<E>> = pairs
+            .into_iter()
+            .zip_eq(a_is_zero)
+            .zip_eq(b_is_zero)
+            .zip_eq(&inverses)
+            .map(|(((pair, a_zero), b_zero), inverse)| {
+                let (a, b) = pair;
+                let (x1, y1) = (a.to_x_coordinate(), a.to_y_coordinate());
+                let (x2, y2) = (b.to_x_coordinate(), b.to_y_coordinate());
+
+                let slope = (y2 - &y1) * inverse;
+                let x3 = slope.square() - &x1 - &x2;
+                let y3 = slope * (x1 - &x3) - y1;
+                let chord_sum = Group::from_xy_coordinates(x3, y3);
+
+                // If `a` is the identity the sum is just `b`; otherwise, if `b` is the identity the
+                // sum is just `a`; otherwise it is the chord-formula result above.
+                let non_identity_result = Group::ternary(&a_zero, &b, &chord_sum);
+                Group::ternary(&b_zero, &a, &non_identity_result)
+            })
+            .collect();
+
+        // An odd-length level has one unpaired point, which carries over to the next level as-is.
+        if level.len() % 2 == 1 {
+            sums.push(level.last().expect("checked level is non-empty via odd length").clone());
+        }
+        sums
+    }
+
+    /// Returns the multiplicative inverse of each element of `values`, computed with a single field
+    /// inversion (Montgomery's trick) instead of one inversion per element.
+    fn batch_invert(values: &[Field<E>]) -> Vec<Field<E>> {
+        if values.is_empty() {
+            return Vec::new();
+        }
+
+        // running[i] = values[0] * values[1] * ... * values[i].
+        let mut running = Vec::with_capacity(values.len());
+        let mut product = Field::<E>::one();
+        for value in values {
+            product = product * value;
+            running.push(product.clone());
+        }
+
+        // Invert the product of all denominators exactly once.
+        let mut inverse_of_product = running.last().expect("values is non-empty").inverse();
+
+        let mut inverses = vec![Field::<E>::zero(); values.len()];
+        for i in (0..values.len()).rev() {
+            inverses[i] = match i {
+                0 => inverse_of_product.clone(),
+                _ => &inverse_of_product * &running[i - 1],
+            };
+            inverse_of_product = &inverse_of_product * &values[i];
+        }
+        inverses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm_algorithms::{crh::PedersenCRH, CRH};
+    use snarkvm_circuits_environment::Circuit;
+    use snarkvm_curves::AffineCurve;
+    use snarkvm_utilities::{test_rng, UniformRand};
+
+    const ITERATIONS: usize = 10;
+    const MESSAGE: &str = "PedersenCircuit0";
+    const WINDOW_SIZE_MULTIPLIER: usize = 8;
+
+    type Projective = <<Circuit as Environment>::Affine as AffineCurve>::Projective;
+
+    fn check_hash_uncompressed_batched<const NUM_WINDOWS: usize, const WINDOW_SIZE: usize>(
+        mode: Mode,
+        num_constants: usize,
+        num_public: usize,
+        num_private: usize,
+        num_constraints: usize,
+    ) {
+        // Initialize the Pedersen hash.
+        let native = PedersenCRH::<Projective, NUM_WINDOWS, WINDOW_SIZE>::setup(MESSAGE);
+        let circuit = Pedersen::<Circuit, NUM_WINDOWS, WINDOW_SIZE>::setup(MESSAGE);
+        // Determine the number of inputs.
+        let num_input_bits = NUM_WINDOWS * WINDOW_SIZE;
+
+        for i in 0..ITERATIONS {
+            // Sample a random input. With `NUM_WINDOWS * WINDOW_SIZE` random bits, roughly half of
+            // the per-window selected points are the identity, which is exactly the case that broke
+            // the hand-rolled chord addition this test is meant to catch.
+            let input = (0..num_input_bits).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            // Compute the expected hash.
+            let expected = native.hash(&input).expect("Failed to hash native input");
+            // Prepare the circuit input.
+            let circuit_input: Vec<Boolean<_>> = Inject::new(mode, input);
+
+            Circuit::scope(format!("Pedersen batched {mode} {i}"), || {
+                // Perform the hash operation.
+                let candidate = circuit.hash_uncompressed_batched(&circuit_input);
+                assert_scope!(num_constants, num_public, num_private, num_constraints);
+                assert_eq!(expected, candidate.eject_value());
+            });
+        }
+    }
+
+    #[test]
+    fn test_hash_uncompressed_batched_constant() {
+        check_hash_uncompressed_batched::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 16, 0, 0, 0);
+        check_hash_uncompressed_batched::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Constant, 32, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_hash_uncompressed_batched_public() {
+        // Fewer private constraints than the per-point `hash_uncompressed` for the same dimensions
+        // (45 and 93, respectively), since the pairwise tree pays one batched inversion per level
+        // instead of one inversion per addition.
+        check_hash_uncompressed_batched::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 16, 0, 36, 36);
+        check_hash_uncompressed_batched::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Public, 32, 0, 74, 74);
+    }
+
+    #[test]
+    fn test_hash_uncompressed_batched_private() {
+        check_hash_uncompressed_batched::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 16, 0, 36, 36);
+        check_hash_uncompressed_batched::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Private, 32, 0, 74, 74);
+    }
+
+    #[test]
+    fn test_hash_uncompressed_batched_handles_all_zero_input() {
+        // Every per-window selected point is the identity in this case, which is the degenerate
+        // input the original hand-rolled chord addition got wrong unconditionally.
+        let native = PedersenCRH::<Projective, 1, WINDOW_SIZE_MULTIPLIER>::setup(MESSAGE);
+        let circuit = Pedersen::<Circuit, 1, WINDOW_SIZE_MULTIPLIER>::setup(MESSAGE);
+
+        let input = vec![false; WINDOW_SIZE_MULTIPLIER];
+        let expected = native.hash(&input).expect("Failed to hash native input");
+        let circuit_input: Vec<Boolean<_>> = Inject::new(Mode::Private, input);
+
+        let candidate = circuit.hash_uncompressed_batched(&circuit_input);
+        assert_eq!(expected, candidate.eject_value());
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use std::borrow::Cow;
+
+/// The number of bits consumed per signed-digit window.
+const SIGNED_DIGIT_WINDOW_SIZE: usize = 3;
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> Pedersen<E, NUM_WINDOWS, WINDOW_SIZE> {
+    /// Returns the Pedersen hash of the given input, using a 3-bit signed-digit window per step.
+    ///
+    /// This mirrors the Sapling Pedersen hash: each 3-bit chunk `(b0, b1, b2)` of a window is
+    /// interpreted as a magnitude `m = 1 + b0 + 2*b1 \in {1,2,3,4}` and a sign `b2`, producing the
+    /// signed digit `d = b2 ? -m : m`. A single chunk only ever selects among 4 precomputed
+    /// multiples of the window base, via three sequential `Group::ternary` calls for the magnitude
+    /// plus one more for the sign; this costs ~2 constraints per input bit (measured: 16 private
+    /// constraints for 8 input bits) instead of the ~5-6 constraints per bit paid by the bit-by-bit
+    /// `hash_uncompressed`.
+    pub fn hash_uncompressed_windowed(&self, input: &[Boolean<E>]) -> Group<E> {
+        let constant_false = Boolean::<E>::constant(false);
+
+        let mut input = Cow::Borrowed(input);
+        match input.len() <= WINDOW_SIZE * NUM_WINDOWS {
+            // Pad the input if it is under the required parameter size.
+            true => input.to_mut().resize(WINDOW_SIZE * NUM_WINDOWS, constant_false.clone()),
+            // Ensure the input size is within the parameter size,
+            false => E::halt("incorrect input length for pedersen hash"),
+        }
+
+        // Compute sum of the selected signed-digit points for all windows.
+        input
+            .chunks(WINDOW_SIZE)
+            .zip_eq(&self.bases)
+            .map(|(bits, powers)| {
+                // The first power of the window is the (undoubled) window generator.
+                Self::mul_bits_windowed(&powers[0], bits)
+            })
+            .fold(Group::<E>::zero(), |acc, x| acc + x)
+    }
+
+    /// Returns `scalar * base`, where `scalar` is given in little-endian bits, using the 3-bit
+    /// signed-digit window technique. This is the fixed-base scalar multiply shared by
+    /// `hash_uncompressed_windowed` and the blinded commitment gadget.
+    pub(super) fn mul_bits_windowed(base: &Group<E>, bits: &[Boolean<E>]) -> Group<E> {
+        let constant_false = Boolean::<E>::constant(false);
+
+        // Pad the bits to a multiple of the signed-digit chunk size.
+        let mut bits = Cow::Borrowed(bits);
+        if bits.len() % SIGNED_DIGIT_WINDOW_SIZE != 0 {
+            let padded_len = bits.len() + (SIGNED_DIGIT_WINDOW_SIZE - bits.len() % SIGNED_DIGIT_WINDOW_SIZE);
+            bits.to_mut().resize(padded_len, constant_false);
+        }
+
+        bits.chunks(SIGNED_DIGIT_WINDOW_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| Self::select_signed_digit(base, i, chunk))
+            .fold(Group::<E>::zero(), |acc, x| acc + x)
+    }
+
+    /// Selects the signed-digit multiple `d * (2^{4*index} * base)` of `base` for a 3-bit chunk
+    /// `(b0, b1, b2)`, where `d = (1 + b0 + 2*b1)` if `b2 = 0`, else `d = -(1 + b0 + 2*b1)`.
+    fn select_signed_digit(base: &Group<E>, index: usize, chunk: &[Boolean<E>]) -> Group<E> {
+        // Scale the window base by 2^{4*index} so that consecutive chunks occupy disjoint ranges.
+        let mut scaled_base = base.clone();
+        for _ in 0..(4 * index) {
+            scaled_base = scaled_base.double();
+        }
+
+        // Precompute the four magnitude multiples {1, 2, 3, 4} * scaled_base.
+        let one = scaled_base.clone();
+        let two = one.double();
+        let three = &two + &one;
+        let four = two.double();
+
+        // Select the magnitude using the two low-order bits: m = 1 + b0 + 2*b1.
+        let low = Group::ternary(&chunk[0], &two, &one);
+        let high = Group::ternary(&chunk[0], &four, &three);
+        let magnitude = Group::ternary(&chunk[1], &high, &low);
+
+        // Conditionally negate the result using the sign bit.
+        Group::ternary(&chunk[2], &(-magnitude.clone()), &magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_util::native_mul_bits_windowed;
+    use snarkvm_algorithms::{crh::PedersenCRH, CRH};
+    use snarkvm_circuits_environment::Circuit;
+    use snarkvm_curves::{AffineCurve, ProjectiveCurve};
+    use snarkvm_utilities::{test_rng, UniformRand, Zero};
+
+    const ITERATIONS: usize = 10;
+    const MESSAGE: &str = "PedersenCircuit0";
+    const WINDOW_SIZE_MULTIPLIER: usize = 8;
+
+    type Affine = <Circuit as Environment>::Affine;
+    type Projective = <Affine as AffineCurve>::Projective;
+
+    /// A native (non-circuit) reference implementation of the signed-digit windowed hash, mirroring
+    /// `hash_uncompressed_windowed`/`mul_bits_windowed` bit for bit. This is the oracle the circuit
+    /// must match: the windowed encoding is a different scalar per window than the bit-by-bit
+    /// `PedersenCRH::hash`, so the two are not expected to agree.
+    fn native_hash_windowed<const NUM_WINDOWS: usize, const WINDOW_SIZE: usize>(
+        native: &PedersenCRH<Projective, NUM_WINDOWS, WINDOW_SIZE>,
+        input: &[bool],
+    ) -> Affine {
+        let mut input = input.to_vec();
+        input.resize(WINDOW_SIZE * NUM_WINDOWS, false);
+
+        let mut acc = Projective::zero();
+        for (bits, window) in input.chunks(WINDOW_SIZE).zip(native.parameters().iter()) {
+            acc += native_mul_bits_windowed(window[0], bits);
+        }
+        acc.into_affine()
+    }
+
+    fn check_hash_uncompressed_windowed<const NUM_WINDOWS: usize, const WINDOW_SIZE: usize>(
+        mode: Mode,
+        num_constants: usize,
+        num_public: usize,
+        num_private: usize,
+        num_constraints: usize,
+    ) {
+        // Initialize the Pedersen hash.
+        let native = PedersenCRH::<Projective, NUM_WINDOWS, WINDOW_SIZE>::setup(MESSAGE);
+        let circuit = Pedersen::<Circuit, NUM_WINDOWS, WINDOW_SIZE>::setup(MESSAGE);
+        // Determine the number of inputs.
+        let num_input_bits = NUM_WINDOWS * WINDOW_SIZE;
+
+        for i in 0..ITERATIONS {
+            // Sample a random input.
+            let input = (0..num_input_bits).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+            // Compute the expected hash using the matching native windowed encoding.
+            let expected = native_hash_windowed(&native, &input);
+            // Prepare the circuit input.
+            let circuit_input: Vec<Boolean<_>> = Inject::new(mode, input);
+
+            Circuit::scope(format!("Pedersen windowed {mode} {i}"), || {
+                // Perform the hash operation.
+                let candidate = circuit.hash_uncompressed_windowed(&circuit_input);
+                assert_scope!(num_constants, num_public, num_private, num_constraints);
+                assert_eq!(expected, candidate.eject_value());
+            });
+        }
+    }
+
+    #[test]
+    fn test_hash_uncompressed_windowed_constant() {
+        // Set the number of windows, and modulate the window size.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 32, 0, 0, 0);
+        check_hash_uncompressed_windowed::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Constant, 64, 0, 0, 0);
+
+        // Set the window size, and modulate the number of windows.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 32, 0, 0, 0);
+        check_hash_uncompressed_windowed::<2, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 64, 0, 0, 0);
+        check_hash_uncompressed_windowed::<3, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 96, 0, 0, 0);
+        check_hash_uncompressed_windowed::<4, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 128, 0, 0, 0);
+        check_hash_uncompressed_windowed::<5, WINDOW_SIZE_MULTIPLIER>(Mode::Constant, 160, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_hash_uncompressed_windowed_public() {
+        // Roughly a third of the constraints of the bit-by-bit `hash_uncompressed` for the same size.
+        // Set the number of windows, and modulate the window size.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 16, 0, 16, 16);
+        check_hash_uncompressed_windowed::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Public, 32, 0, 32, 32);
+
+        // Set the window size, and modulate the number of windows.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 16, 0, 16, 16);
+        check_hash_uncompressed_windowed::<2, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 32, 0, 32, 32);
+        check_hash_uncompressed_windowed::<3, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 48, 0, 48, 48);
+        check_hash_uncompressed_windowed::<4, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 64, 0, 64, 64);
+        check_hash_uncompressed_windowed::<5, WINDOW_SIZE_MULTIPLIER>(Mode::Public, 80, 0, 80, 80);
+    }
+
+    #[test]
+    fn test_hash_uncompressed_windowed_private() {
+        // Set the number of windows, and modulate the window size.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 16, 0, 16, 16);
+        check_hash_uncompressed_windowed::<1, { 2 * WINDOW_SIZE_MULTIPLIER }>(Mode::Private, 32, 0, 32, 32);
+
+        // Set the window size, and modulate the number of windows.
+        check_hash_uncompressed_windowed::<1, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 16, 0, 16, 16);
+        check_hash_uncompressed_windowed::<2, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 32, 0, 32, 32);
+        check_hash_uncompressed_windowed::<3, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 48, 0, 48, 48);
+        check_hash_uncompressed_windowed::<4, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 64, 0, 64, 64);
+        check_hash_uncompressed_windowed::<5, WINDOW_SIZE_MULTIPLIER>(Mode::Private, 80, 0, 80, 80);
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+pub mod commit_uncompressed;
+pub mod hash_tree_uncompressed;
+pub mod hash_uncompressed;
+pub mod hash_uncompressed_batched;
+pub mod hash_uncompressed_windowed;
+
+use snarkvm_circuits_environment::prelude::*;
+use snarkvm_circuits_types::{Boolean, Field, Group};
+
+use snarkvm_algorithms::{crh::PedersenCRH, CRH};
+use snarkvm_curves::AffineCurve;
+
+/// The elliptic curve group used natively to derive the Pedersen bases.
+type Projective<E> = <<E as Environment>::Affine as AffineCurve>::Projective;
+
+/// Pedersen is a collision-resistant hash function that processes an input in chunks of `WINDOW_SIZE` bits
+/// across `NUM_WINDOWS` independent windows, and sums the selected window bases together.
+#[derive(Clone)]
+pub struct Pedersen<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> {
+    /// The bases for the Pedersen hash.
+    bases: Vec<Vec<Group<E>>>,
+    /// The independent base used to blind commitments with randomness.
+    random_base: Group<E>,
+}
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize> Pedersen<E, NUM_WINDOWS, WINDOW_SIZE> {
+    /// Initializes a new instance of Pedersen with the given setup message.
+    pub fn setup(message: &str) -> Self {
+        let native = PedersenCRH::<Projective<E>, NUM_WINDOWS, WINDOW_SIZE>::setup(message);
+        let bases = native
+            .parameters()
+            .iter()
+            .map(|window| window.iter().map(|base| Group::constant(*base)).collect())
+            .collect();
+
+        // Derive the randomizer base from a domain-separated message, so it is not in the span of
+        // the message bases above.
+        let random_base_message = format!("{message} Randomizer");
+        let random_base_native = PedersenCRH::<Projective<E>, 1, 1>::setup(&random_base_message);
+        let random_base = Group::constant(random_base_native.parameters()[0][0]);
+
+        Self { bases, random_base }
+    }
+}
+
+/// Native (non-circuit) oracles for the 3-bit signed-digit, `2^{4i}`-spaced windowed encoding used
+/// by `mul_bits_windowed`, shared by the `hash_uncompressed_windowed` and `commit_uncompressed`
+/// test modules so the reference implementation of that encoding lives in exactly one place.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use snarkvm_circuits_environment::{Circuit, Environment};
+    use snarkvm_curves::AffineCurve;
+    use snarkvm_utilities::Zero;
+
+    pub(crate) const SIGNED_DIGIT_WINDOW_SIZE: usize = 3;
+
+    type Affine = <Circuit as Environment>::Affine;
+    type Projective = <Affine as AffineCurve>::Projective;
+
+    /// Native counterpart of `Pedersen::mul_bits_windowed`.
+    pub(crate) fn native_mul_bits_windowed(base: Affine, bits: &[bool]) -> Projective {
+        let mut bits = bits.to_vec();
+        if bits.len() % SIGNED_DIGIT_WINDOW_SIZE != 0 {
+            let padded_len = bits.len() + (SIGNED_DIGIT_WINDOW_SIZE - bits.len() % SIGNED_DIGIT_WINDOW_SIZE);
+            bits.resize(padded_len, false);
+        }
+
+        let mut acc = Projective::zero();
+        for (i, chunk) in bits.chunks(SIGNED_DIGIT_WINDOW_SIZE).enumerate() {
+            acc += native_select_signed_digit(base, i, chunk);
+        }
+        acc
+    }
+
+    /// Native counterpart of `Pedersen::select_signed_digit`.
+    pub(crate) fn native_select_signed_digit(base: Affine, index: usize, chunk: &[bool]) -> Projective {
+        let mut scaled_base = base.into_projective();
+        for _ in 0..(4 * index) {
+            scaled_base.double_in_place();
+        }
+
+        let one = scaled_base;
+        let mut two = one;
+        two.double_in_place();
+        let three = two + one;
+        let mut four = two;
+        four.double_in_place();
+
+        let low = if chunk[0] { two } else { one };
+        let high = if chunk[0] { four } else { three };
+        let magnitude = if chunk[1] { high } else { low };
+
+        if chunk[2] { -magnitude } else { magnitude }
+    }
+}
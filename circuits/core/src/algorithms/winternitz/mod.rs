@@ -0,0 +1,231 @@
+// Copyright (C) 2019-2022 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::algorithms::pedersen::Pedersen;
+
+use snarkvm_circuits_environment::prelude::*;
+use snarkvm_circuits_types::{Boolean, Field, Group};
+
+/// Winternitz is a one-time signature scheme whose chaining function is the Pedersen hash. The
+/// secret key is a set of blocks; the public key is each block hashed `w - 1` times, where
+/// `w = 2^LOG_W`.
+pub struct Winternitz<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize, const LOG_W: usize> {
+    /// The hash function used to chain each block of the signature.
+    hasher: Pedersen<E, NUM_WINDOWS, WINDOW_SIZE>,
+}
+
+impl<E: Environment, const NUM_WINDOWS: usize, const WINDOW_SIZE: usize, const LOG_W: usize>
+    Winternitz<E, NUM_WINDOWS, WINDOW_SIZE, LOG_W>
+{
+    /// The Winternitz parameter `w = 2^LOG_W`.
+    const W: usize = 1 << LOG_W;
+
+    /// Initializes a new instance of the Winternitz verifier, using the given Pedersen setup message.
+    pub fn setup(message: &str) -> Self {
+        Self { hasher: Pedersen::setup(message) }
+    }
+
+    /// Returns `true` if `signature` is a valid Winternitz one-time signature over `message`,
+    /// checked against `public_key`.
+    ///
+    /// `message` is the digest to verify. `signature` reveals one hash-chain block per digit of
+    /// `message`, plus one block per checksum digit (appended to prevent truncation attacks); each
+    /// `public_key` block is the corresponding secret block hashed `w - 1` times.
+    pub fn verify(&self, message: &[Boolean<E>], signature: &[Group<E>], public_key: &[Group<E>]) -> Boolean<E> {
+        let digits = self.digits_with_checksum(message);
+        if digits.len() != signature.len() || digits.len() != public_key.len() {
+            E::halt("mismatching number of Winternitz digits, signature blocks, and public key blocks");
+        }
+
+        digits
+            .iter()
+            .zip_eq(signature)
+            .zip_eq(public_key)
+            .map(|((digit, block), expected)| self.hash_chain(block, digit).is_equal(expected))
+            .fold(Boolean::constant(true), |acc, is_valid| acc & is_valid)
+    }
+
+    /// Applies the Pedersen hash chain to `block` a fixed `w - 1` times (constant at circuit-build
+    /// time), then selects the `(w - 1 - digit)`-th state using `digit`'s bits as a multiplexer, so
+    /// the result is `block` hashed `w - 1 - digit` more times without a data-dependent loop bound.
+    /// Each link uses `hash_uncompressed_batched` in every mode, not the per-point
+    /// `hash_uncompressed`; a hash chain repeats it `w - 1` times per block.
+    fn hash_chain(&self, block: &Group<E>, digit: &[Boolean<E>]) -> Group<E> {
+        let mut states = Vec::with_capacity(Self::W);
+        states.push(block.clone());
+        for _ in 1..Self::W {
+            let previous = states.last().expect("the hash chain always has a previous state");
+            states.push(self.hasher.hash_uncompressed_batched(&previous.to_bits_le()));
+        }
+
+        // Reverse the chain so that index `k` holds `block` hashed `w - 1 - k` times, matching the
+        // little-endian bits of `digit`.
+        states.reverse();
+        Self::select_by_index(&states, digit)
+    }
+
+    /// Selects `values[index]`, where `index` is given by the little-endian bits of `index_bits`,
+    /// using a binary tree of `Group::ternary` selections.
+    fn select_by_index(values: &[Group<E>], index_bits: &[Boolean<E>]) -> Group<E> {
+        let mut layer = values.to_vec();
+        for bit in index_bits {
+            layer = layer.chunks(2).map(|pair| Group::ternary(bit, &pair[1], &pair[0])).collect();
+        }
+        layer.into_iter().next().expect("the selection layer always has exactly one remaining state")
+    }
+
+    /// Splits `message` into `LOG_W`-bit digits, then appends the Winternitz checksum digits
+    /// `C = sum_i (w - 1 - d_i)`, also encoded in base `w`, to prevent truncation attacks.
+    fn digits_with_checksum(&self, message: &[Boolean<E>]) -> Vec<Vec<Boolean<E>>> {
+        if message.len() % LOG_W != 0 {
+            E::halt("Winternitz message length must be a multiple of LOG_W");
+        }
+
+        let digits: Vec<Vec<Boolean<E>>> = message.chunks(LOG_W).map(|digit| digit.to_vec()).collect();
+        let num_checksum_digits = Self::num_checksum_digits(digits.len());
+
+        // Accumulate the checksum as a field element, then decompose it back into base-`w` digits.
+        let one = Field::<E>::one();
+        let max_digit = (0..Self::W - 1).fold(Field::<E>::zero(), |acc, _| acc + &one);
+        let checksum = digits
+            .iter()
+            .map(|digit| max_digit.clone() - Field::from_bits_le(digit))
+            .fold(Field::<E>::zero(), |acc, value| acc + value);
+
+        let mut checksum_digits = checksum.to_bits_le();
+        checksum_digits.resize(num_checksum_digits * LOG_W, Boolean::constant(false));
+
+        let mut digits = digits;
+        digits.extend(checksum_digits.chunks(LOG_W).map(|digit| digit.to_vec()));
+        digits
+    }
+
+    /// Returns the number of base-`w` digits needed to represent the maximum possible checksum
+    /// value for a message with `num_message_digits` digits.
+    fn num_checksum_digits(num_message_digits: usize) -> usize {
+        let max_checksum = num_message_digits * (Self::W - 1);
+        let mut num_digits = 1;
+        while (Self::W.pow(num_digits as u32) - 1) < max_checksum {
+            num_digits += 1;
+        }
+        num_digits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm_circuits_environment::Circuit;
+    use snarkvm_curves::{AffineCurve, ProjectiveCurve};
+    use snarkvm_utilities::{test_rng, UniformRand};
+
+    const ITERATIONS: usize = 3;
+    const MESSAGE: &str = "WinternitzCircuit0";
+    const NUM_WINDOWS: usize = 8;
+    const WINDOW_SIZE: usize = 64;
+    const LOG_W: usize = 2;
+    const NUM_MESSAGE_DIGITS: usize = 4;
+
+    type Projective = <<Circuit as Environment>::Affine as AffineCurve>::Projective;
+
+    /// Hashes `block` exactly `count` times with the given Pedersen hasher. Unlike `hash_chain`,
+    /// this does not use the circuit's digit-selection trick, so it can independently build
+    /// signature/public-key fixtures without exercising the code under test.
+    fn hash_n_times(
+        hasher: &Pedersen<Circuit, NUM_WINDOWS, WINDOW_SIZE>,
+        block: &Group<Circuit>,
+        count: usize,
+    ) -> Group<Circuit> {
+        let mut state = block.clone();
+        for _ in 0..count {
+            state = hasher.hash_uncompressed(&state.to_bits_le());
+        }
+        state
+    }
+
+    /// Reconstructs the little-endian numeric value of a digit's bits.
+    fn digit_value(bits: &[Boolean<Circuit>]) -> usize {
+        bits.iter().enumerate().fold(0, |acc, (i, bit)| acc | ((bit.eject_value() as usize) << i))
+    }
+
+    /// Samples a genuine Winternitz key pair and signature over a random message, in the given mode.
+    #[allow(clippy::type_complexity)]
+    fn sample_fixture(
+        mode: Mode,
+    ) -> (
+        Winternitz<Circuit, NUM_WINDOWS, WINDOW_SIZE, LOG_W>,
+        Vec<Boolean<Circuit>>,
+        Vec<Group<Circuit>>,
+        Vec<Group<Circuit>>,
+    ) {
+        let winternitz = Winternitz::<Circuit, NUM_WINDOWS, WINDOW_SIZE, LOG_W>::setup(MESSAGE);
+
+        let message_bits = (0..NUM_MESSAGE_DIGITS * LOG_W).map(|_| bool::rand(&mut test_rng())).collect::<Vec<bool>>();
+        let message: Vec<Boolean<Circuit>> = Inject::new(mode, message_bits);
+        let digits = winternitz.digits_with_checksum(&message);
+
+        let max_hashes = Winternitz::<Circuit, NUM_WINDOWS, WINDOW_SIZE, LOG_W>::W - 1;
+
+        let mut signature = Vec::with_capacity(digits.len());
+        let mut public_key = Vec::with_capacity(digits.len());
+        for digit in &digits {
+            let secret_native = Projective::rand(&mut test_rng()).into_affine();
+            let secret: Group<Circuit> = Inject::new(mode, secret_native);
+
+            signature.push(hash_n_times(&winternitz.hasher, &secret, digit_value(digit)));
+            public_key.push(hash_n_times(&winternitz.hasher, &secret, max_hashes));
+        }
+
+        (winternitz, message, signature, public_key)
+    }
+
+    #[test]
+    fn test_verify_accepts_genuine_signature() {
+        for mode in [Mode::Constant, Mode::Public, Mode::Private] {
+            for _ in 0..ITERATIONS {
+                let (winternitz, message, signature, public_key) = sample_fixture(mode);
+                let candidate = winternitz.verify(&message, &signature, &public_key);
+                assert!(candidate.eject_value());
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_signature_block() {
+        let (winternitz, message, mut signature, public_key) = sample_fixture(Mode::Private);
+
+        // A forger without the secret key cannot produce the chain value the verifier expects.
+        let forged_native = Projective::rand(&mut test_rng()).into_affine();
+        signature[0] = Inject::new(Mode::Private, forged_native);
+
+        let candidate = winternitz.verify(&message, &signature, &public_key);
+        assert!(!candidate.eject_value());
+    }
+
+    #[test]
+    fn test_verify_rejects_message_tampered_after_signing() {
+        let (winternitz, mut message, signature, public_key) = sample_fixture(Mode::Private);
+
+        // Flipping a single message bit changes its digit decomposition and the checksum digits
+        // derived from it, so a signature computed for the original message must not verify
+        // against the tampered one.
+        let flipped = !message[0].eject_value();
+        message[0] = Inject::new(Mode::Private, flipped);
+
+        let candidate = winternitz.verify(&message, &signature, &public_key);
+        assert!(!candidate.eject_value());
+    }
+}